@@ -0,0 +1,235 @@
+//! A compact storage encoding for OP receipts.
+//!
+//! This mirrors reth's `Compact` storage codec: instead of the RLP wire form,
+//! a receipt is written as a leading flags byte followed by its fields packed
+//! back to back. The flags record which optional fields are present and how the
+//! status is represented, so the scalar fields need no per-field framing. The
+//! logs are the one exception: they are stored as their RLP encoding behind a
+//! varint length prefix.
+//!
+//! Because a receipt's bloom is fully derivable from its logs, the `WithBloom`
+//! variant only writes the 256-byte bloom inline when it is *not* the bloom the
+//! logs hash to; otherwise it is dropped and recomputed with
+//! [`OpDepositReceipt::bloom_slow`] on read, saving 256 bytes per receipt.
+
+use super::{OpDepositReceipt, OpDepositReceiptWithBloom};
+use alloy_consensus::{Eip658Value, Receipt};
+use alloy_primitives::{Bloom, Log, B256};
+use alloy_rlp::{Decodable, Encodable};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// `deposit_nonce` is present.
+const FLAG_DEPOSIT_NONCE: u8 = 0b0000_0001;
+/// `deposit_receipt_version` is present.
+const FLAG_DEPOSIT_RECEIPT_VERSION: u8 = 0b0000_0010;
+/// The status is a 32-byte post-state root rather than an EIP-658 boolean.
+const FLAG_POST_STATE: u8 = 0b0000_0100;
+/// The bloom is stored inline (it is not derivable from the logs).
+const FLAG_BLOOM: u8 = 0b0000_1000;
+
+/// Appends the unsigned LEB128 encoding of `value` to `buf`, returning the
+/// number of bytes written.
+fn put_varint(mut value: u128, buf: &mut Vec<u8>) -> usize {
+    let mut written = 0;
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        written += 1;
+        if value == 0 {
+            return written;
+        }
+    }
+}
+
+/// Reads an unsigned LEB128 value from the front of `buf`, returning the value
+/// and the number of bytes consumed.
+fn get_varint(buf: &[u8]) -> (u128, usize) {
+    let mut value = 0u128;
+    let mut shift = 0;
+    for (i, &byte) in buf.iter().enumerate() {
+        value |= u128::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return (value, i + 1);
+        }
+        shift += 7;
+    }
+    (value, buf.len())
+}
+
+impl OpDepositReceipt {
+    /// Writes the flags byte and packed fields of this receipt into `buf`,
+    /// OR-ing `extra_flags` into the flags byte. Returns the number of bytes
+    /// written.
+    fn write_compact(&self, extra_flags: u8, buf: &mut Vec<u8>) -> usize {
+        let mut flags = extra_flags;
+        if self.deposit_nonce.is_some() {
+            flags |= FLAG_DEPOSIT_NONCE;
+        }
+        if self.deposit_receipt_version.is_some() {
+            flags |= FLAG_DEPOSIT_RECEIPT_VERSION;
+        }
+        if matches!(self.inner.status, Eip658Value::PostState(_)) {
+            flags |= FLAG_POST_STATE;
+        }
+        buf.push(flags);
+        let mut written = 1;
+
+        match self.inner.status {
+            Eip658Value::Eip658(success) => {
+                buf.push(success as u8);
+                written += 1;
+            }
+            Eip658Value::PostState(root) => {
+                buf.extend_from_slice(root.as_slice());
+                written += 32;
+            }
+        }
+
+        written += put_varint(self.inner.cumulative_gas_used, buf);
+
+        let mut logs = Vec::new();
+        self.inner.logs.encode(&mut logs);
+        written += put_varint(logs.len() as u128, buf);
+        buf.extend_from_slice(&logs);
+        written += logs.len();
+
+        if let Some(nonce) = self.deposit_nonce {
+            written += put_varint(nonce as u128, buf);
+        }
+        if let Some(version) = self.deposit_receipt_version {
+            written += put_varint(version as u128, buf);
+        }
+        written
+    }
+
+    /// Reads the flags byte and packed fields from the front of `buf`,
+    /// returning the receipt, the number of bytes consumed, and the raw flags
+    /// byte (so callers can recover variant-specific flags such as the bloom).
+    fn read_compact(buf: &[u8]) -> (Self, usize, u8) {
+        let flags = buf[0];
+        let mut pos = 1;
+
+        let status = if flags & FLAG_POST_STATE != 0 {
+            let root = B256::from_slice(&buf[pos..pos + 32]);
+            pos += 32;
+            Eip658Value::PostState(root)
+        } else {
+            let success = buf[pos] != 0;
+            pos += 1;
+            Eip658Value::Eip658(success)
+        };
+
+        let (cumulative_gas_used, n) = get_varint(&buf[pos..]);
+        pos += n;
+
+        let (logs_len, n) = get_varint(&buf[pos..]);
+        pos += n;
+        let logs_len = logs_len as usize;
+        let logs = Vec::<Log>::decode(&mut &buf[pos..pos + logs_len]).expect("valid compact logs");
+        pos += logs_len;
+
+        let deposit_nonce = (flags & FLAG_DEPOSIT_NONCE != 0).then(|| {
+            let (value, n) = get_varint(&buf[pos..]);
+            pos += n;
+            value as u64
+        });
+        let deposit_receipt_version = (flags & FLAG_DEPOSIT_RECEIPT_VERSION != 0).then(|| {
+            let (value, n) = get_varint(&buf[pos..]);
+            pos += n;
+            value as u64
+        });
+
+        let receipt = Self {
+            inner: Receipt { status, cumulative_gas_used, logs },
+            deposit_nonce,
+            deposit_receipt_version,
+        };
+        (receipt, pos, flags)
+    }
+
+    /// Encodes the receipt into its compact form, returning the number of bytes
+    /// written to `buf`.
+    pub fn to_compact(&self, buf: &mut Vec<u8>) -> usize {
+        self.write_compact(0, buf)
+    }
+
+    /// Decodes a receipt from its compact form in `buf` (whose total length is
+    /// `len`), returning the receipt and the number of bytes consumed.
+    pub fn from_compact(buf: &[u8], len: usize) -> (Self, usize) {
+        let (receipt, consumed, _) = Self::read_compact(&buf[..len]);
+        (receipt, consumed)
+    }
+}
+
+impl OpDepositReceiptWithBloom {
+    /// Encodes the receipt into its compact form, returning the number of bytes
+    /// written to `buf`. The bloom is written inline only when it cannot be
+    /// recomputed from the logs.
+    pub fn to_compact(&self, buf: &mut Vec<u8>) -> usize {
+        let derivable = self.logs_bloom == self.receipt.bloom_slow();
+        let extra = if derivable { 0 } else { FLAG_BLOOM };
+        let mut written = self.receipt.write_compact(extra, buf);
+        if !derivable {
+            buf.extend_from_slice(self.logs_bloom.as_slice());
+            written += 256;
+        }
+        written
+    }
+
+    /// Decodes a receipt from its compact form in `buf` (whose total length is
+    /// `len`), returning the receipt and the number of bytes consumed. When the
+    /// bloom was dropped it is recomputed from the logs.
+    pub fn from_compact(buf: &[u8], len: usize) -> (Self, usize) {
+        let buf = &buf[..len];
+        let (receipt, mut consumed, flags) = OpDepositReceipt::read_compact(buf);
+        let logs_bloom = if flags & FLAG_BLOOM != 0 {
+            let bloom = Bloom::from_slice(&buf[consumed..consumed + 256]);
+            consumed += 256;
+            bloom
+        } else {
+            receipt.bloom_slow()
+        };
+        (Self { receipt, logs_bloom }, consumed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbitrary::{Arbitrary, Unstructured};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn receipt_roundtrip(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&bytes);
+            if let Ok(receipt) = OpDepositReceipt::arbitrary(&mut u) {
+                let mut buf = Vec::new();
+                let written = receipt.to_compact(&mut buf);
+                prop_assert_eq!(written, buf.len());
+                let (decoded, consumed) = OpDepositReceipt::from_compact(&buf, buf.len());
+                prop_assert_eq!(consumed, written);
+                prop_assert_eq!(decoded, receipt);
+            }
+        }
+
+        #[test]
+        fn with_bloom_roundtrip(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&bytes);
+            if let Ok(receipt) = OpDepositReceiptWithBloom::arbitrary(&mut u) {
+                let mut buf = Vec::new();
+                let written = receipt.to_compact(&mut buf);
+                prop_assert_eq!(written, buf.len());
+                let (decoded, consumed) = OpDepositReceiptWithBloom::from_compact(&buf, buf.len());
+                prop_assert_eq!(consumed, written);
+                prop_assert_eq!(decoded, receipt);
+            }
+        }
+    }
+}
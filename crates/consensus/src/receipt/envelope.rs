@@ -0,0 +1,226 @@
+//! The [`OpReceiptEnvelope`] type, an EIP-2718 typed receipt for all OP
+//! transaction types.
+
+use super::{OpDepositReceiptWithBloom, OpTxReceipt, DEPOSIT_RECEIPT_TYPE};
+use alloy_consensus::{Eip658Value, Receipt, ReceiptWithBloom, TxReceipt};
+use alloy_primitives::{Bloom, Log};
+use alloy_rlp::{BufMut, Decodable, Encodable};
+
+/// Receipt envelope, as defined in [EIP-2718], modified for the OP Stack.
+///
+/// This enum distinguishes between the receipt types of every OP transaction.
+/// It is a single type that downstream consumers can decode any OP receipt
+/// into, whether it comes off the wire or from RPC, without having to know the
+/// transaction type out of band.
+///
+/// Like the Ethereum [`ReceiptEnvelope`], non-legacy receipts are encoded as
+/// `type_byte || rlp(payload)`; legacy receipts are a bare RLP list.
+///
+/// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+/// [`ReceiptEnvelope`]: alloy_consensus::ReceiptEnvelope
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum OpReceiptEnvelope<T = Log> {
+    /// Receipt envelope with no type flag.
+    #[cfg_attr(feature = "serde", serde(rename = "0x0", alias = "0x00"))]
+    Legacy(ReceiptWithBloom<Receipt<T>>),
+    /// Receipt envelope with type flag 1, containing a [EIP-2930] receipt.
+    ///
+    /// [EIP-2930]: https://eips.ethereum.org/EIPS/eip-2930
+    #[cfg_attr(feature = "serde", serde(rename = "0x1", alias = "0x01"))]
+    Eip2930(ReceiptWithBloom<Receipt<T>>),
+    /// Receipt envelope with type flag 2, containing a [EIP-1559] receipt.
+    ///
+    /// [EIP-1559]: https://eips.ethereum.org/EIPS/eip-1559
+    #[cfg_attr(feature = "serde", serde(rename = "0x2", alias = "0x02"))]
+    Eip1559(ReceiptWithBloom<Receipt<T>>),
+    /// Receipt envelope with type flag 4, containing a [EIP-7702] receipt.
+    ///
+    /// [EIP-7702]: https://eips.ethereum.org/EIPS/eip-7702
+    #[cfg_attr(feature = "serde", serde(rename = "0x4", alias = "0x04"))]
+    Eip7702(ReceiptWithBloom<Receipt<T>>),
+    /// Receipt envelope with type flag 126, containing an OP deposit receipt.
+    #[cfg_attr(feature = "serde", serde(rename = "0x7e"))]
+    Deposit(OpDepositReceiptWithBloom<T>),
+}
+
+impl OpReceiptEnvelope<Log> {
+    /// Returns the EIP-2718 type byte for this receipt, or `None` for a legacy
+    /// receipt.
+    pub const fn tx_type(&self) -> Option<u8> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Eip2930(_) => Some(0x01),
+            Self::Eip1559(_) => Some(0x02),
+            Self::Eip7702(_) => Some(0x04),
+            Self::Deposit(_) => Some(DEPOSIT_RECEIPT_TYPE),
+        }
+    }
+
+    /// Return true if the receipt is a deposit receipt.
+    pub const fn is_deposit(&self) -> bool {
+        matches!(self, Self::Deposit(_))
+    }
+
+    /// Return the inner receipt as a [`TxReceipt`] trait object, regardless of
+    /// the variant.
+    fn as_receipt(&self) -> &dyn TxReceipt<Log> {
+        match self {
+            Self::Legacy(r) | Self::Eip2930(r) | Self::Eip1559(r) | Self::Eip7702(r) => r,
+            Self::Deposit(r) => r,
+        }
+    }
+
+    /// The length of the inner receipt's consensus encoding, without the type
+    /// byte.
+    fn inner_length(&self) -> usize {
+        match self {
+            Self::Legacy(r) | Self::Eip2930(r) | Self::Eip1559(r) | Self::Eip7702(r) => r.length(),
+            Self::Deposit(r) => r.length(),
+        }
+    }
+
+    /// Encodes the inner receipt, without the type byte.
+    fn encode_inner(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(r) | Self::Eip2930(r) | Self::Eip1559(r) | Self::Eip7702(r) => {
+                r.encode(out)
+            }
+            Self::Deposit(r) => r.encode(out),
+        }
+    }
+}
+
+impl Encodable for OpReceiptEnvelope<Log> {
+    fn encode(&self, out: &mut dyn BufMut) {
+        if let Some(ty) = self.tx_type() {
+            out.put_u8(ty);
+        }
+        self.encode_inner(out);
+    }
+
+    fn length(&self) -> usize {
+        self.inner_length() + self.tx_type().is_some() as usize
+    }
+}
+
+impl Decodable for OpReceiptEnvelope<Log> {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let first = *buf.first().ok_or(alloy_rlp::Error::InputTooShort)?;
+
+        // A leading byte `>= 0xc0` is an RLP list header, i.e. a legacy receipt.
+        if first >= alloy_rlp::EMPTY_LIST_CODE {
+            return Ok(Self::Legacy(Decodable::decode(buf)?));
+        }
+
+        // Otherwise the first byte is the EIP-2718 type flag; strip it and
+        // dispatch to the matching variant.
+        let mut payload = &buf[1..];
+        let this = match first {
+            0x01 => Self::Eip2930(Decodable::decode(&mut payload)?),
+            0x02 => Self::Eip1559(Decodable::decode(&mut payload)?),
+            0x04 => Self::Eip7702(Decodable::decode(&mut payload)?),
+            DEPOSIT_RECEIPT_TYPE => Self::Deposit(Decodable::decode(&mut payload)?),
+            _ => return Err(alloy_rlp::Error::Custom("unknown receipt type byte")),
+        };
+        *buf = payload;
+        Ok(this)
+    }
+}
+
+impl TxReceipt for OpReceiptEnvelope<Log> {
+    fn status_or_post_state(&self) -> Eip658Value {
+        self.as_receipt().status_or_post_state()
+    }
+
+    fn status(&self) -> bool {
+        self.as_receipt().status()
+    }
+
+    fn bloom(&self) -> Bloom {
+        self.as_receipt().bloom()
+    }
+
+    fn bloom_cheap(&self) -> Option<Bloom> {
+        self.as_receipt().bloom_cheap()
+    }
+
+    fn cumulative_gas_used(&self) -> u128 {
+        self.as_receipt().cumulative_gas_used()
+    }
+
+    fn logs(&self) -> &[Log] {
+        self.as_receipt().logs()
+    }
+}
+
+impl OpTxReceipt for OpReceiptEnvelope<Log> {
+    fn deposit_nonce(&self) -> Option<u64> {
+        match self {
+            Self::Deposit(r) => r.deposit_nonce(),
+            _ => None,
+        }
+    }
+
+    fn deposit_receipt_version(&self) -> Option<u64> {
+        match self {
+            Self::Deposit(r) => r.deposit_receipt_version(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::OpDepositReceipt;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    fn deposit_envelope() -> OpReceiptEnvelope {
+        OpReceiptEnvelope::Deposit(
+            OpDepositReceipt {
+                inner: Receipt { status: true.into(), cumulative_gas_used: 46_913, logs: vec![] },
+                deposit_nonce: Some(4_012_991),
+                deposit_receipt_version: Some(1),
+            }
+            .with_bloom(),
+        )
+    }
+
+    #[test]
+    fn deposit_roundtrip_keeps_type_byte() {
+        let envelope = deposit_envelope();
+        let mut buf = Vec::new();
+        envelope.encode(&mut buf);
+
+        assert_eq!(buf.first(), Some(&DEPOSIT_RECEIPT_TYPE));
+        assert_eq!(buf.len(), envelope.length());
+
+        let decoded = OpReceiptEnvelope::decode(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, envelope);
+        assert_eq!(decoded.deposit_receipt_version(), Some(1));
+    }
+
+    #[test]
+    fn legacy_receipt_is_a_bare_list() {
+        let envelope = OpReceiptEnvelope::Legacy(
+            Receipt { status: false.into(), cumulative_gas_used: 1, logs: vec![] }.with_bloom(),
+        );
+        let mut buf = Vec::new();
+        envelope.encode(&mut buf);
+
+        assert!(buf[0] >= alloy_rlp::EMPTY_LIST_CODE);
+        let decoded = OpReceiptEnvelope::decode(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, envelope);
+        assert_eq!(decoded.deposit_nonce(), None);
+    }
+
+    #[test]
+    fn unknown_type_byte_is_rejected() {
+        let data = [0x05u8, 0xc0];
+        assert!(OpReceiptEnvelope::decode(&mut &data[..]).is_err());
+    }
+}
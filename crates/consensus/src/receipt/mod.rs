@@ -0,0 +1,31 @@
+//! Receipt types for Optimism.
+
+mod receipts;
+pub use receipts::{OpDepositReceipt, OpDepositReceiptWithBloom, OpReceiptFork};
+
+#[cfg(feature = "compact")]
+mod compact;
+
+mod envelope;
+pub use envelope::OpReceiptEnvelope;
+
+pub mod trie;
+pub use trie::{
+    receipt_proof, receipt_proof_with_encoder, receipts_root, receipts_root_with_encoder,
+    verify_receipt_proof,
+};
+
+use alloy_consensus::TxReceipt;
+
+/// The EIP-2718 type byte for OP deposit receipts.
+pub(crate) const DEPOSIT_RECEIPT_TYPE: u8 = 0x7e;
+
+/// Receipt type that knows about the OP Stack's deposit-specific fields.
+pub trait OpTxReceipt: TxReceipt {
+    /// Returns the deposit nonce of the transaction, if it is a deposit.
+    fn deposit_nonce(&self) -> Option<u64>;
+
+    /// Returns the deposit receipt version of the transaction, if it is a
+    /// post-Canyon deposit.
+    fn deposit_receipt_version(&self) -> Option<u64>;
+}
@@ -1,12 +1,47 @@
 //! Transaction receipt types for Optimism.
 
-use super::OpTxReceipt;
+use super::{OpTxReceipt, DEPOSIT_RECEIPT_TYPE};
 use alloy_consensus::{Eip658Value, Receipt, TxReceipt};
-use alloy_primitives::{Bloom, Log};
+use alloy_primitives::{keccak256, Bloom, Log, B256};
 use alloy_rlp::{length_of_length, BufMut, Decodable, Encodable};
 
 use core::borrow::Borrow;
 
+/// The OP Stack hardfork that governs how a deposit receipt is hashed into the
+/// receipts trie.
+///
+/// The `deposit_receipt_version` field changes which deposit-specific fields
+/// are folded into the receipt's trie hash, so a root computed from these types
+/// only matches a node's `receiptsRoot` when the active fork is known. Note
+/// that `deposit_receipt_version` is only ever set for post-Canyon deposit
+/// transactions. The encoding is chosen by the fork passed in, not by which
+/// deposit fields the receipt has set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpReceiptFork {
+    /// The original Bedrock encoding: neither `deposit_nonce` nor
+    /// `deposit_receipt_version` is part of the hashed payload.
+    Bedrock,
+    /// Regolith introduced `deposit_nonce` on the receipt, but — matching the
+    /// historical consensus behaviour — it is *not* folded into the trie hash.
+    Regolith,
+    /// Canyon hashes both `deposit_nonce` and `deposit_receipt_version` into the
+    /// receipt.
+    Canyon,
+}
+
+impl OpReceiptFork {
+    /// Whether `deposit_nonce` is part of the hashed payload for this fork.
+    const fn hashes_deposit_nonce(self) -> bool {
+        matches!(self, Self::Canyon)
+    }
+
+    /// Whether `deposit_receipt_version` is part of the hashed payload for this
+    /// fork.
+    const fn hashes_deposit_receipt_version(self) -> bool {
+        matches!(self, Self::Canyon)
+    }
+}
+
 /// Receipt containing result of transaction execution.
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -183,6 +218,59 @@ impl OpDepositReceiptWithBloom {
             + self.receipt.deposit_receipt_version.map_or(0, |version| version.length())
     }
 
+    /// The length of the payload hashed into the receipts trie for `fork`.
+    fn trie_payload_len(&self, fork: OpReceiptFork) -> usize {
+        let mut len = self.receipt.inner.status.length()
+            + self.receipt.inner.cumulative_gas_used.length()
+            + self.logs_bloom.length()
+            + self.receipt.inner.logs.length();
+        if fork.hashes_deposit_nonce() {
+            len += self.receipt.deposit_nonce.map_or(0, |nonce| nonce.length());
+        }
+        if fork.hashes_deposit_receipt_version() {
+            len += self.receipt.deposit_receipt_version.map_or(0, |version| version.length());
+        }
+        len
+    }
+
+    /// Encodes the receipt as it is hashed into the receipts trie for the given
+    /// `fork`.
+    ///
+    /// Unlike [`encode_fields`](Self::encode_fields), this emits the EIP-2718
+    /// type byte and selects the fork-correct set of deposit fields: Bedrock and
+    /// Regolith omit `deposit_nonce` and `deposit_receipt_version` from the
+    /// hash, while Canyon includes both. Using [`Encodable::encode`] to build a
+    /// trie value instead would silently disagree with nodes across the
+    /// Regolith→Canyon boundary.
+    pub fn encode_for_trie(&self, fork: OpReceiptFork, out: &mut dyn BufMut) {
+        out.put_u8(DEPOSIT_RECEIPT_TYPE);
+        alloy_rlp::Header { list: true, payload_length: self.trie_payload_len(fork) }.encode(out);
+        self.receipt.inner.status.encode(out);
+        self.receipt.inner.cumulative_gas_used.encode(out);
+        self.logs_bloom.encode(out);
+        self.receipt.inner.logs.encode(out);
+        if fork.hashes_deposit_nonce() {
+            if let Some(nonce) = self.receipt.deposit_nonce {
+                nonce.encode(out);
+            }
+        }
+        if fork.hashes_deposit_receipt_version() {
+            if let Some(version) = self.receipt.deposit_receipt_version {
+                version.encode(out);
+            }
+        }
+    }
+
+    /// The keccak256 hash of the fork-correct trie encoding, i.e. the bytes a
+    /// node folds into the receipts trie for this receipt.
+    pub fn trie_hash(&self, fork: OpReceiptFork) -> B256 {
+        #[cfg(not(feature = "std"))]
+        use alloc::vec::Vec;
+        let mut out = Vec::new();
+        self.encode_for_trie(fork, &mut out);
+        keccak256(out)
+    }
+
     /// Returns the rlp header for the receipt payload.
     fn receipt_rlp_header(&self) -> alloy_rlp::Header {
         alloy_rlp::Header { list: true, payload_length: self.payload_len() }
@@ -424,4 +512,53 @@ mod tests {
         expected.encode(&mut buf);
         assert_eq!(buf, &data[..]);
     }
+
+    #[test]
+    fn trie_hash_is_fork_sensitive() {
+        // A Canyon deposit receipt carries a version; its trie hash must include
+        // both deposit fields, whereas Regolith hashes neither.
+        let receipt = OpDepositReceipt {
+            inner: Receipt { cumulative_gas_used: 46913, logs: vec![], status: true.into() },
+            deposit_nonce: Some(4012991),
+            deposit_receipt_version: Some(1),
+        }
+        .with_bloom();
+
+        let mut regolith = Vec::new();
+        receipt.encode_for_trie(OpReceiptFork::Regolith, &mut regolith);
+        let mut canyon = Vec::new();
+        receipt.encode_for_trie(OpReceiptFork::Canyon, &mut canyon);
+
+        assert_eq!(regolith.first(), Some(&0x7e));
+        assert_ne!(regolith, canyon);
+        assert_ne!(
+            receipt.trie_hash(OpReceiptFork::Regolith),
+            receipt.trie_hash(OpReceiptFork::Canyon)
+        );
+
+        // Bedrock and Regolith fold in no deposit fields, so they agree.
+        assert_eq!(
+            receipt.trie_hash(OpReceiptFork::Bedrock),
+            receipt.trie_hash(OpReceiptFork::Regolith)
+        );
+
+        // Pin the exact hashed bytes. Canyon is the 2718-typed form (0x7e) of
+        // the full post-Canyon payload, i.e. `post_canyon_receipt_roundtrip`'s
+        // vector with both deposit fields present.
+        let mut expected_canyon = vec![0x7eu8];
+        expected_canyon.extend_from_slice(&hex!("f9010d0182b741b9010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c0833d3bbf01"));
+        assert_eq!(canyon, expected_canyon);
+
+        // Regolith drops both deposit fields from the hashed payload, so its
+        // encoding equals that of the same receipt with no nonce or version.
+        let stripped = OpDepositReceipt {
+            inner: receipt.receipt.inner.clone(),
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        }
+        .with_bloom();
+        let mut expected_regolith = Vec::new();
+        stripped.encode_for_trie(OpReceiptFork::Regolith, &mut expected_regolith);
+        assert_eq!(regolith, expected_regolith);
+    }
 }
@@ -0,0 +1,248 @@
+//! Receipts Merkle-Patricia trie helpers.
+//!
+//! Builds the canonical receipts trie the way consensus does so that light
+//! clients and provers can derive a block's `receiptsRoot` and check that a
+//! single receipt is included, without pulling in a full execution client.
+//!
+//! For each receipt at block index `i` the trie key is `rlp(i)` (the minimal
+//! RLP encoding of the integer index) and the value is the *consensus* encoding
+//! of the receipt: the EIP-2718 typed form `type_byte || rlp(payload)`, folding
+//! in exactly the deposit fields the active fork hashes. Keys are ordered by
+//! their RLP-encoded bytes, which is *not* the numeric order: `rlp(0) == [0x80]`
+//! sorts after `rlp(1)..=rlp(127) == [0x01]..=[0x7f]` and before
+//! `rlp(128) == [0x81, 0x80]`.
+//!
+//! The `*_with_encoder` functions take the per-receipt encoder as a closure
+//! (mirroring reth's `ordered_trie_root_with_encoder`); the plain
+//! [`receipts_root`]/[`receipt_proof`] wrap them for [`OpDepositReceiptWithBloom`]
+//! with the fork-correct [`OpDepositReceiptWithBloom::encode_for_trie`].
+
+use super::{OpDepositReceiptWithBloom, OpReceiptFork};
+use alloy_primitives::{Bytes, B256};
+use alloy_trie::{
+    proof::{verify_proof, ProofRetainer, ProofVerificationError},
+    HashBuilder, Nibbles,
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+/// Encodes `index` with minimal RLP, matching how the receipts trie keys a
+/// receipt by its position in the block.
+fn trie_key(index: usize) -> Vec<u8> {
+    use alloy_rlp::Encodable;
+    let mut key = Vec::new();
+    (index as u64).encode(&mut key);
+    key
+}
+
+/// Returns the `(key, value)` pairs for a set of receipts, sorted into the
+/// nibble order the Patricia trie expects. `encode` produces each leaf's
+/// consensus bytes.
+fn trie_entries<R>(receipts: &[R], encode: impl Fn(&R, &mut Vec<u8>)) -> Vec<(Nibbles, Bytes)> {
+    let mut entries: Vec<(Nibbles, Bytes)> = receipts
+        .iter()
+        .enumerate()
+        .map(|(index, receipt)| {
+            let mut value = Vec::new();
+            encode(receipt, &mut value);
+            (Nibbles::unpack(trie_key(index)), Bytes::from(value))
+        })
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+/// Computes the receipts trie root for `receipts`, using `encode` to produce
+/// each leaf's consensus bytes.
+///
+/// Mirrors reth's `ordered_trie_root_with_encoder`: callers inject the exact
+/// per-receipt encoding so the root matches the block header's `receiptsRoot`.
+pub fn receipts_root_with_encoder<R>(
+    receipts: &[R],
+    encode: impl Fn(&R, &mut Vec<u8>),
+) -> B256 {
+    let mut hash_builder = HashBuilder::default();
+    for (key, value) in trie_entries(receipts, encode) {
+        hash_builder.add_leaf(key, value.as_ref());
+    }
+    hash_builder.root()
+}
+
+/// Produces the inclusion proof for the receipt at `index`, using `encode` to
+/// produce each leaf's consensus bytes.
+///
+/// The returned list is the ordered set of RLP-encoded trie nodes along the
+/// path from the root to the leaf, suitable for [`verify_receipt_proof`].
+pub fn receipt_proof_with_encoder<R>(
+    receipts: &[R],
+    index: usize,
+    encode: impl Fn(&R, &mut Vec<u8>),
+) -> Vec<Bytes> {
+    let target = Nibbles::unpack(trie_key(index));
+    let mut hash_builder =
+        HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target]));
+    for (key, value) in trie_entries(receipts, encode) {
+        hash_builder.add_leaf(key, value.as_ref());
+    }
+    hash_builder.root();
+    hash_builder.take_proof_nodes().into_nodes_sorted().into_iter().map(|(_, node)| node).collect()
+}
+
+/// Computes the receipts trie root for a block of OP deposit receipts on the
+/// given `fork`.
+///
+/// Each leaf is the fork-correct consensus encoding from
+/// [`OpDepositReceiptWithBloom::encode_for_trie`], so the root matches the block
+/// header's `receiptsRoot` across the Regolith→Canyon boundary.
+pub fn receipts_root(receipts: &[OpDepositReceiptWithBloom], fork: OpReceiptFork) -> B256 {
+    receipts_root_with_encoder(receipts, |receipt, out| receipt.encode_for_trie(fork, out))
+}
+
+/// Produces the inclusion proof for the deposit receipt at `index` on the given
+/// `fork`.
+pub fn receipt_proof(
+    receipts: &[OpDepositReceiptWithBloom],
+    index: usize,
+    fork: OpReceiptFork,
+) -> Vec<Bytes> {
+    receipt_proof_with_encoder(receipts, index, |receipt, out| receipt.encode_for_trie(fork, out))
+}
+
+/// Verifies that `value` is the receipt stored at `index` under `root`, given
+/// the `proof` returned by [`receipt_proof`]. `value` must be the same
+/// consensus bytes used to build the root.
+pub fn verify_receipt_proof(
+    root: B256,
+    index: usize,
+    value: &[u8],
+    proof: &[Bytes],
+) -> Result<(), ProofVerificationError> {
+    verify_proof(root, Nibbles::unpack(trie_key(index)), Some(value.to_vec()), proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::{OpDepositReceipt, OpDepositReceiptWithBloom};
+    use alloy_consensus::Receipt;
+    use alloy_primitives::keccak256;
+    use alloy_rlp::Encodable;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    fn sample_receipt(gas: u128) -> OpDepositReceiptWithBloom {
+        OpDepositReceipt {
+            inner: Receipt { status: true.into(), cumulative_gas_used: gas, logs: vec![] },
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        }
+        .with_bloom()
+    }
+
+    fn trie_value(receipt: &OpDepositReceiptWithBloom, fork: OpReceiptFork) -> Vec<u8> {
+        let mut value = Vec::new();
+        receipt.encode_for_trie(fork, &mut value);
+        value
+    }
+
+    #[test]
+    fn single_receipt_proof_roundtrips() {
+        let fork = OpReceiptFork::Bedrock;
+        let receipts = vec![sample_receipt(21_000)];
+        let root = receipts_root(&receipts, fork);
+        let proof = receipt_proof(&receipts, 0, fork);
+
+        assert!(verify_receipt_proof(root, 0, &trie_value(&receipts[0], fork), &proof).is_ok());
+    }
+
+    #[test]
+    fn proof_survives_non_numeric_key_ordering() {
+        // 200 receipts puts rlp(0) == 0x80 between the one-byte keys 1..=127
+        // and the two-byte keys from 128 on, so rlp-byte order and numeric
+        // order diverge.
+        let fork = OpReceiptFork::Bedrock;
+        let receipts: Vec<_> = (0..200u128).map(sample_receipt).collect();
+        let root = receipts_root(&receipts, fork);
+
+        for index in [0usize, 127, 128, 199] {
+            let proof = receipt_proof(&receipts, index, fork);
+            assert!(verify_receipt_proof(
+                root,
+                index,
+                &trie_value(&receipts[index], fork),
+                &proof
+            )
+            .is_ok());
+        }
+    }
+
+    #[test]
+    fn root_uses_consensus_leaf_encoding() {
+        // A single-receipt block: the trie is one leaf node keyed by
+        // rlp(0) == 0x80. Its root is keccak256 of the leaf node
+        // `rlp([compact_path, value])`, where `value` is the 2718-typed receipt
+        // (0x7e || rlp(payload)) — *not* the bare RLP list.
+        let fork = OpReceiptFork::Canyon;
+        let receipt = OpDepositReceipt {
+            inner: Receipt { status: true.into(), cumulative_gas_used: 46_913, logs: vec![] },
+            deposit_nonce: Some(4_012_991),
+            deposit_receipt_version: Some(1),
+        }
+        .with_bloom();
+
+        let value = trie_value(&receipt, fork);
+        // Consensus value carries the deposit type byte.
+        assert_eq!(value.first(), Some(&0x7eu8));
+
+        // Independently build the one-leaf MPT root. Key 0x80 has nibbles
+        // [8, 0] (even length) → compact leaf path 0x20, 0x80.
+        let path: &[u8] = &[0x20, 0x80];
+        let mut leaf = Vec::new();
+        [path, value.as_slice()][..].encode(&mut leaf);
+        let expected = keccak256(leaf);
+
+        assert_eq!(receipts_root(&[receipt.clone()], fork), expected);
+
+        // A root built from the bare list encoding (no type byte) cannot match
+        // the consensus root, which is exactly the bug this change fixes.
+        let bare = receipts_root_with_encoder(&[receipt], |r, out| r.encode(out));
+        assert_ne!(bare, expected);
+    }
+
+    #[test]
+    fn root_is_fork_correct_across_regolith_canyon() {
+        // A deposit receipt carrying a nonce (Regolith) vs. the same receipt
+        // promoted to Canyon with a receipt version.
+        let regolith = OpDepositReceipt {
+            inner: Receipt { status: true.into(), cumulative_gas_used: 46_913, logs: vec![] },
+            deposit_nonce: Some(4_012_991),
+            deposit_receipt_version: None,
+        }
+        .with_bloom();
+        let canyon = OpDepositReceipt {
+            deposit_receipt_version: Some(1),
+            ..regolith.receipt.clone()
+        }
+        .with_bloom();
+
+        // Regolith does not fold the nonce into the hash, so its root equals the
+        // root of the same receipt with no nonce at all.
+        let no_nonce = OpDepositReceipt {
+            deposit_nonce: None,
+            ..regolith.receipt.clone()
+        }
+        .with_bloom();
+        assert_eq!(
+            receipts_root(&[regolith.clone()], OpReceiptFork::Regolith),
+            receipts_root(&[no_nonce], OpReceiptFork::Regolith)
+        );
+
+        // Canyon folds both deposit fields in, so its root differs.
+        assert_ne!(
+            receipts_root(&[regolith], OpReceiptFork::Regolith),
+            receipts_root(&[canyon], OpReceiptFork::Canyon)
+        );
+    }
+}